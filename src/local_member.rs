@@ -1,5 +1,43 @@
 use crate::Memory;
 
+/// Walks an offset chain, checking that every intermediate pointer dereferenced along the way --
+/// and the final resolved address -- is non-null, and returns the resolved address as a typed
+/// [`NonNull<T>`]. A null pointer is never a valid address, even for zero-sized accesses, so a
+/// zero produced anywhere in the chain is rejected, not just at the final step.
+///
+/// Shared by [`LocalMember::validate`] and [`LocalDynMember::get_offset`] so the chain-walking
+/// logic only needs to live in one place.
+///
+/// [`NonNull<T>`]: https://doc.rust-lang.org/std/ptr/struct.NonNull.html
+fn resolve_offset_chain<T>(offsets: &[usize]) -> std::io::Result<std::ptr::NonNull<T>> {
+    let (last, init) = offsets.split_last().ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::Other, "No offsets set!")
+    })?;
+    let mut offset = 0_usize;
+    for (i, o) in init.iter().enumerate() {
+        offset = offset.wrapping_add(*o);
+        if std::ptr::NonNull::new(offset as *mut usize).is_none() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!("Offset {} would be a null dereference!", i),
+            ));
+        }
+        // We can't guarantee alignment, so we must use `read_unaligned()`
+        // to ensure that its ok to read from, as `read()` requires that
+        // our source pointer is properly aligned.
+        unsafe {
+            offset = (offset as *const usize).read_unaligned();
+        }
+    }
+    offset = offset.wrapping_add(*last);
+    std::ptr::NonNull::new(offset as *mut T).ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::Other,
+            "Final resolved address would be a null dereference!",
+        )
+    })
+}
+
 /// This struct provides functions for modifying the memory of a program from within the address
 /// space of that program. This may be helpful for debug functions, or for an injected DLL.
 ///
@@ -66,6 +104,121 @@ impl<T: Sized + Copy> LocalMember<T> {
             _phantom: std::marker::PhantomData,
         }
     }
+
+    /// Reads `count` contiguous values of `T` starting at the resolved offset into a `Vec<T>`,
+    /// resolving the offset chain only once instead of once per element. This is useful for
+    /// dumping arrays, strings, and structs-of-arrays in one go.
+    ///
+    /// A `count` of `0` returns an empty `Vec` without touching memory or resolving the offset
+    /// chain.
+    ///
+    /// # Safety
+    ///
+    /// The resolved address must point to at least `count` valid, readable values of `T`.
+    pub unsafe fn read_n(&self, count: usize) -> std::io::Result<Vec<T>> {
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+        let offset = self.get_offset()? as *const T;
+        let mut values = Vec::<T>::with_capacity(count);
+        // We can't guarantee alignment, so each element is read with `read_unaligned()`, the
+        // path `Memory::read` falls back to for an unaligned address.
+        for i in 0..count {
+            values.push(offset.add(i).read_unaligned());
+        }
+        Ok(values)
+    }
+
+    /// Writes `values` to contiguous memory starting at the resolved offset, resolving the
+    /// offset chain only once instead of once per element. This is useful for patching arrays,
+    /// strings, and structs-of-arrays in one go.
+    ///
+    /// An empty `values` slice is a no-op that does not touch memory or resolve the offset chain.
+    ///
+    /// This will only return an error if no offsets are set or one of the offsets gives a null
+    /// pointer.
+    pub fn write_slice(&self, values: &[T]) -> std::io::Result<()> {
+        if values.is_empty() {
+            return Ok(());
+        }
+        let offset = self.get_offset()? as *mut T;
+        unsafe {
+            // We can't guarantee alignment, so each element is written with `write_unaligned()`,
+            // the path `Memory::write` falls back to for an unaligned address.
+            for (i, value) in values.iter().enumerate() {
+                offset.add(i).write_unaligned(*value);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns whether the resolved address is naturally aligned for `T`.
+    ///
+    /// This resolves the offset chain and checks [`pointer::align_offset`], which returns the
+    /// number of elements that would need to be skipped to reach an aligned address (or
+    /// `usize::MAX` if that's not possible for this pointer). A result of `0` means the pointer
+    /// is already aligned, which is the fast path [`Memory::read`] and [`Memory::write`] use to
+    /// skip the unaligned read/write.
+    ///
+    /// [`pointer::align_offset`]: https://doc.rust-lang.org/std/primitive.pointer.html#method.align_offset
+    pub fn is_aligned(&self) -> std::io::Result<bool> {
+        let offset = self.get_offset()? as *const T;
+        Ok(offset.align_offset(std::mem::align_of::<T>()) == 0)
+    }
+
+    /// Resolves the offset chain and reinterprets the following `len * size_of::<T>()` bytes as
+    /// `U` elements, mirroring [`slice::align_to`]: the returned middle slice is the largest
+    /// contiguous run of properly aligned `U` elements, and the prefix/suffix are the leftover
+    /// unaligned bytes at the start and end. This lets a buffer read as `u8` be reinterpreted as
+    /// `u32`/`f32` and similar without copying, even when `size_of::<T>()` and `size_of::<U>()`
+    /// don't divide evenly into one another.
+    ///
+    /// # Safety
+    ///
+    /// The resolved address must point to at least `len * size_of::<T>()` valid, readable bytes,
+    /// and the returned slices borrow that memory for the lifetime of `self`.
+    pub unsafe fn cast_slice<U>(&self, len: usize) -> std::io::Result<(&[u8], &[U], &[u8])> {
+        let offset = self.get_offset()? as *const u8;
+        let bytes = std::slice::from_raw_parts(offset, len * std::mem::size_of::<T>());
+        Ok(bytes.align_to::<U>())
+    }
+
+    /// Walks the full offset chain, checking that every intermediate pointer dereferenced along
+    /// the way -- and the final resolved address -- is non-null, and returns the resolved
+    /// address as a typed [`NonNull<T>`]. A null pointer is never a valid address, even for
+    /// zero-sized accesses, so a zero produced anywhere in the chain is rejected, not just at the
+    /// final step.
+    ///
+    /// [`read`](#method.read) and [`write`](#method.write) call this internally, so this is the
+    /// one place callers need to check reachability before committing a write.
+    ///
+    /// [`NonNull<T>`]: https://doc.rust-lang.org/std/ptr/struct.NonNull.html
+    pub fn validate(&self) -> std::io::Result<std::ptr::NonNull<T>> {
+        resolve_offset_chain(&self.offsets)
+    }
+
+    /// Writes `count * size_of::<T>()` copies of `byte` starting at the resolved offset,
+    /// wrapping [`std::ptr::write_bytes`]. This covers zeroing out a structure, poisoning a freed
+    /// region, or blanking a buffer before a fresh write, without allocating a throwaway slice of
+    /// `T`.
+    ///
+    /// A `count` of `0` is a no-op that does not touch memory or resolve the offset chain.
+    ///
+    /// This will only return an error if no offsets are set or one of the offsets gives a null
+    /// pointer.
+    pub fn fill(&self, byte: u8, count: usize) -> std::io::Result<()> {
+        if count == 0 {
+            return Ok(());
+        }
+        // `write_bytes` requires its pointer to be aligned for `T`, which the resolved address
+        // isn't guaranteed to be; writing through a `*mut u8` (whose alignment requirement is
+        // always satisfied) sidesteps that, scaling the count up to a byte count accordingly.
+        let offset = self.validate()?.as_ptr() as *mut u8;
+        unsafe {
+            std::ptr::write_bytes(offset, byte, count * std::mem::size_of::<T>());
+        }
+        Ok(())
+    }
 }
 
 impl<T: Sized + Copy> Memory<T> for LocalMember<T> {
@@ -74,47 +227,120 @@ impl<T: Sized + Copy> Memory<T> for LocalMember<T> {
     }
 
     fn get_offset(&self) -> std::io::Result<usize> {
-        let mut offset = 0_usize;
-        for i in 0..self.offsets.len() - 1 {
-            offset = offset.wrapping_add(self.offsets[i]);
-            if offset == 0 {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    "Would be a null dereference!",
-                ));
-            }
-            // We can't guarantee alignment, so we must use `read_unaligned()`
-            // to ensure that its ok to read from, as `read()` requires that
-            // our source pointer is properly aligned.
-            unsafe {
-                offset = (offset as *const usize).read_unaligned();
-            }
-        }
-        Ok(offset.wrapping_add(self.offsets[self.offsets.len() - 1]))
+        self.validate().map(|p| p.as_ptr() as usize)
     }
 
     /// This will only return a error if one of the offsets gives a null pointer. or give a
     /// non-aligned read
     unsafe fn read(&self) -> std::io::Result<T> {
-        let offset = self.get_offset()? as *const T;
-        // Read the value of the pointer. We can't guarantee alignment, so this
-        // is `read_unaligned()` instead of `read()`
-        let x: T = offset.read_unaligned();
+        let offset = self.validate()?.as_ptr() as *const T;
+        // If the resolved address happens to already be aligned for `T` we can use the faster
+        // `read()`; otherwise we fall back to `read_unaligned()` since we can't guarantee
+        // alignment in general.
+        let x: T = if offset.align_offset(std::mem::align_of::<T>()) == 0 {
+            offset.read()
+        } else {
+            offset.read_unaligned()
+        };
         Ok(x)
     }
 
     /// This will only return a error if one of the offsets gives a null pointer.
     fn write(&self, value: &T) -> std::io::Result<()> {
-        use std::ptr::copy_nonoverlapping;
-
-        let offset = self.get_offset()? as *mut T;
+        let offset = self.validate()?.as_ptr();
         unsafe {
-            copy_nonoverlapping(value, offset, 1_usize);
+            // If the resolved address happens to already be aligned for `T` we can store
+            // directly; otherwise we fall back to `write_unaligned()` since we can't guarantee
+            // alignment in general.
+            if (offset as *const T).align_offset(std::mem::align_of::<T>()) == 0 {
+                *offset = *value;
+            } else {
+                offset.write_unaligned(*value);
+            }
         }
         Ok(())
     }
 }
 
+/// A [`LocalMember`]-like handle for addressing a `[T]` whose length is only known at runtime,
+/// such as a length-prefixed buffer. `LocalMember<T>`'s `read`/`write` only ever move a single
+/// `T`, with no way to say how many trailing elements to read; `LocalDynMember` instead stores
+/// that count alongside the offset chain so callers can read a runtime-sized `[T]` in one call.
+///
+/// This only covers a runtime-length `[T]` where `T: Sized`. It does not address addressing a
+/// trailing variable-length-array struct or other genuinely unsized type by value -- doing that
+/// soundly would mean reconstructing a fat pointer over the *target* memory, which the
+/// `Sized`-only `T: Copy` bound here can't express.
+///
+/// [`LocalMember`]: struct.LocalMember.html
+#[derive(Clone, Debug, Default)]
+pub struct LocalDynMember<T> {
+    offsets: Vec<usize>,
+    len: usize,
+    _phantom: std::marker::PhantomData<*mut T>,
+}
+
+impl<T: Copy> LocalDynMember<T> {
+    /// Creates a new `LocalDynMember` with no offsets and a slice length of `0`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            offsets: Vec::new(),
+            len: 0,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Creates a new `LocalDynMember` with a given set of offsets and slice length.
+    #[must_use]
+    pub fn new_offset(offsets: Vec<usize>, len: usize) -> Self {
+        Self {
+            offsets,
+            len,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Sets the offsets used to resolve this member's address.
+    pub fn set_offset(&mut self, new_offsets: Vec<usize>) {
+        self.offsets = new_offsets;
+    }
+
+    /// Sets the runtime length of the addressed `[T]`.
+    pub fn set_len(&mut self, len: usize) {
+        self.len = len;
+    }
+
+    /// Resolves the offset chain, the same way [`LocalMember::get_offset`] does.
+    ///
+    /// [`LocalMember::get_offset`]: struct.LocalMember.html#method.get_offset
+    pub fn get_offset(&self) -> std::io::Result<usize> {
+        resolve_offset_chain::<T>(&self.offsets).map(|p| p.as_ptr() as usize)
+    }
+
+    /// Reads the addressed `[T]` into an owned, boxed slice, using the runtime length set via
+    /// [`set_len`](#method.set_len). Each element is read element-by-element with
+    /// `read_unaligned` since the resolved address is not guaranteed to be aligned for `T`.
+    ///
+    /// A length of `0` -- including the default, freshly-[`new`](#method.new)ed state -- returns
+    /// an empty boxed slice without touching memory or resolving the offset chain.
+    ///
+    /// # Safety
+    ///
+    /// The resolved address must point to at least `self.len` valid, readable values of `T`.
+    pub unsafe fn read_unsized(&self) -> std::io::Result<Box<[T]>> {
+        if self.len == 0 {
+            return Ok(Vec::new().into_boxed_slice());
+        }
+        let offset = self.get_offset()? as *const T;
+        let mut values = Vec::<T>::with_capacity(self.len);
+        for i in 0..self.len {
+            values.push(offset.add(i).read_unaligned());
+        }
+        Ok(values.into_boxed_slice())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -154,4 +380,101 @@ mod test {
         member.write(&0xffff).unwrap();
         assert_eq!(test, 0xffff);
     }
+    #[test]
+    fn read_write_n_local_i32() {
+        let mut test = [1_i32, 2, 3, 4];
+        let mut member = LocalMember::<i32>::new();
+        member.set_offset(vec![test.as_mut_ptr() as usize]);
+        unsafe {
+            // safety: the memory being pointed to is known to be 4 valid i32s as we control it
+            assert_eq!(member.read_n(4).unwrap(), vec![1, 2, 3, 4]);
+            assert!(member.read_n(0).unwrap().is_empty());
+        }
+        member.write_slice(&[5, 6, 7, 8]).unwrap();
+        assert_eq!(test, [5, 6, 7, 8]);
+        member.write_slice(&[]).unwrap();
+        assert_eq!(test, [5, 6, 7, 8]);
+    }
+    #[test]
+    fn is_aligned_local_i32() {
+        let test = 4_i32;
+        let mut member = LocalMember::<i32>::new();
+        member.set_offset(vec![std::ptr::addr_of!(test) as usize]);
+        // an i32 variable is always aligned for i32
+        assert!(member.is_aligned().unwrap());
+    }
+    // `[u8; 8]` only has `align_of::<[u8; 8]>() == 1`, so its address isn't guaranteed to be
+    // 4-byte aligned; over-align the buffer so the `cast_slice` test below is deterministic
+    // instead of passing by luck of stack placement.
+    #[repr(align(4))]
+    struct AlignedBytes([u8; 8]);
+
+    #[test]
+    fn cast_slice_local_u8_to_u32() {
+        let test = AlignedBytes([0xef, 0xbe, 0xad, 0xde, 0xef, 0xbe, 0xad, 0xde]);
+        let mut member = LocalMember::<u8>::new();
+        member.set_offset(vec![test.0.as_ptr() as usize]);
+        unsafe {
+            // safety: `test` is known to hold 8 valid, initialized bytes
+            let (prefix, middle, suffix) = member.cast_slice::<u32>(test.0.len()).unwrap();
+            assert!(prefix.is_empty());
+            assert!(suffix.is_empty());
+            assert_eq!(middle, [0xdead_beef_u32, 0xdead_beef_u32]);
+        }
+    }
+    #[test]
+    fn read_unsized_local_dyn_member() {
+        let test = [1_u8, 2, 3, 4];
+        let mut member = LocalDynMember::<u8>::new();
+        member.set_offset(vec![test.as_ptr() as usize]);
+        member.set_len(test.len());
+        unsafe {
+            // safety: `test` is known to hold 4 valid, initialized bytes
+            assert_eq!(&*member.read_unsized().unwrap(), &test);
+        }
+    }
+    #[test]
+    fn read_unsized_default_local_dyn_member_is_empty() {
+        // the documented default state ("no offsets") must not panic/UB on its own, and a
+        // length of 0 must short-circuit before the offset chain is ever resolved
+        let member = LocalDynMember::<u8>::default();
+        unsafe {
+            // safety: a length of 0 never touches memory
+            assert!(member.read_unsized().unwrap().is_empty());
+        }
+    }
+    #[test]
+    fn validate_rejects_null_final_offset() {
+        // previously only intermediate offsets were checked for null, so a chain that resolves
+        // to a null *final* address would silently succeed
+        let mut member = LocalMember::<i32>::new();
+        member.set_offset(vec![0]);
+        assert!(member.validate().is_err());
+    }
+    #[test]
+    fn validate_rejects_empty_offsets() {
+        // an empty offset chain must be a clean error, not a `len() - 1` underflow
+        let member = LocalMember::<i32>::new();
+        assert!(member.validate().is_err());
+    }
+    #[test]
+    fn validate_accepts_valid_chain() {
+        let test = 4_i32;
+        let mut member = LocalMember::<i32>::new();
+        member.set_offset(vec![std::ptr::addr_of!(test) as usize]);
+        assert_eq!(
+            member.validate().unwrap().as_ptr(),
+            std::ptr::addr_of!(test) as *mut i32
+        );
+    }
+    #[test]
+    fn fill_local_bytes() {
+        let mut test = [1_u8, 2, 3, 4];
+        let mut member = LocalMember::<u8>::new();
+        member.set_offset(vec![test.as_mut_ptr() as usize]);
+        member.fill(0xff, test.len()).unwrap();
+        assert_eq!(test, [0xff; 4]);
+        member.fill(0, 0).unwrap();
+        assert_eq!(test, [0xff; 4]);
+    }
 }